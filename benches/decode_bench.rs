@@ -0,0 +1,56 @@
+//! Benchmarks the slice-backed `BinProtReader` fast path (see `src/reader.rs`) against
+//! the generic `Read`-based `BinProtRead` path for the workloads it was added for:
+//! a large `Vec<i64>` and a `BTreeMap` of small records. Run with `cargo bench`.
+
+use binprot_rs::{BinProtRead, BinProtReadFast, BinProtReader, BinProtWrite};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::collections::BTreeMap;
+
+fn vec_i64(len: usize) -> Vec<i64> {
+    (0..len as i64).collect()
+}
+
+fn btree_map(len: usize) -> BTreeMap<i64, f64> {
+    (0..len as i64).map(|i| (i, i as f64 * 0.5)).collect()
+}
+
+fn encode<B: BinProtWrite>(v: &B) -> Vec<u8> {
+    let mut buf = Vec::new();
+    v.binprot_write(&mut buf).unwrap();
+    buf
+}
+
+fn bench_vec_i64(c: &mut Criterion) {
+    let value = vec_i64(10_000);
+    let bytes = encode(&value);
+
+    let mut group = c.benchmark_group("vec_i64_10000");
+    group.bench_function("read_based", |b| {
+        b.iter(|| Vec::<i64>::binprot_read(&mut bytes.as_slice()).unwrap())
+    });
+    group.bench_function("slice_fast_path", |b| {
+        b.iter(|| {
+            Vec::<i64>::binprot_read_fast(&mut BinProtReader::new(&bytes)).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn bench_btree_map(c: &mut Criterion) {
+    let value = btree_map(10_000);
+    let bytes = encode(&value);
+
+    let mut group = c.benchmark_group("btree_map_10000");
+    group.bench_function("read_based", |b| {
+        b.iter(|| BTreeMap::<i64, f64>::binprot_read(&mut bytes.as_slice()).unwrap())
+    });
+    group.bench_function("slice_fast_path", |b| {
+        b.iter(|| {
+            BTreeMap::<i64, f64>::binprot_read_fast(&mut BinProtReader::new(&bytes)).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_vec_i64, bench_btree_map);
+criterion_main!(benches);
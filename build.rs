@@ -0,0 +1,422 @@
+//! Generates `BinProtRead`/`BinProtWrite`/`BinProtSize`/`BinProtShape` impls from the
+//! declarative schema files in `schema/*.types` (records, variants with explicit
+//! integer tags, `Tuple<A, B, ...>` up to the arities the crate's `tuple_impls!`
+//! macro covers, and the primitives this crate supports). The generated source is
+//! written to `OUT_DIR/generated.rs` and pulled in via `include!` from `src/lib.rs`,
+//! so hand-maintaining impls for hundreds of record and variant types doesn't drift
+//! from a single source of truth.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+enum FieldType {
+    Nat0,
+    Int,
+    Float,
+    Bool,
+    Unit,
+    Str,
+    Option(Box<FieldType>),
+    List(Box<FieldType>),
+    Tuple(Vec<FieldType>),
+    Named(String),
+}
+
+impl FieldType {
+    fn rust_type(&self) -> String {
+        match self {
+            FieldType::Nat0 => "crate::Nat0".to_string(),
+            FieldType::Int => "i64".to_string(),
+            FieldType::Float => "f64".to_string(),
+            FieldType::Bool => "bool".to_string(),
+            FieldType::Unit => "()".to_string(),
+            FieldType::Str => "String".to_string(),
+            FieldType::Option(t) => format!("Option<{}>", t.rust_type()),
+            FieldType::List(t) => format!("Vec<{}>", t.rust_type()),
+            FieldType::Tuple(ts) => {
+                let parts: Vec<String> = ts.iter().map(FieldType::rust_type).collect();
+                format!("({},)", parts.join(", "))
+            }
+            FieldType::Named(n) => n.clone(),
+        }
+    }
+
+    /// Builds the `Shape` expression for this field, used by [`codegen_record`] and
+    /// [`codegen_variant`]. A `Named` field that refers back to `self_name` (the record
+    /// or variant currently being generated) becomes `Shape::Recursive` instead of
+    /// calling `binprot_shape()` on itself, which would recurse forever.
+    fn shape_expr(&self, self_name: &str) -> String {
+        match self {
+            FieldType::Nat0 => "crate::Shape::Nat0".to_string(),
+            FieldType::Int => "crate::Shape::Int".to_string(),
+            FieldType::Float => "crate::Shape::Float".to_string(),
+            FieldType::Bool => "crate::Shape::Bool".to_string(),
+            FieldType::Unit => "crate::Shape::Unit".to_string(),
+            FieldType::Str => "crate::Shape::Str".to_string(),
+            FieldType::Option(t) => format!("crate::Shape::Option(Box::new({}))", t.shape_expr(self_name)),
+            FieldType::List(t) => format!("crate::Shape::List(Box::new({}))", t.shape_expr(self_name)),
+            FieldType::Tuple(ts) => {
+                let parts: Vec<String> = ts.iter().map(|t| t.shape_expr(self_name)).collect();
+                format!("crate::Shape::Tuple(vec![{}])", parts.join(", "))
+            }
+            FieldType::Named(n) if n == self_name => format!("crate::Shape::Recursive({:?})", n),
+            FieldType::Named(n) => format!("<{}>::binprot_shape()", n),
+        }
+    }
+}
+
+struct Field {
+    name: String,
+    ty: FieldType,
+}
+
+struct Record {
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Variant {
+    name: String,
+    ctors: Vec<(String, u64, Vec<FieldType>)>,
+}
+
+enum Item {
+    Record(Record),
+    Variant(Variant),
+}
+
+/// Splits schema source into tokens, treating `{`, `}`, `(`, `)`, `,`, `:`, `;`, `=`,
+/// `<`, `>` as standalone tokens and skipping whole-line comments (lines whose first
+/// non-whitespace character is `;`). A `;` later in a line is always a token, so a
+/// comment cannot follow a field or constructor on the same line.
+fn tokenize(src: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for raw_line in src.lines() {
+        // A line whose first non-whitespace character is `;` is a comment; `;` used as
+        // a statement terminator is always preceded by a field/constructor on the line.
+        if raw_line.trim_start().starts_with(';') {
+            continue;
+        }
+        let mut current = String::new();
+        for c in raw_line.chars() {
+            match c {
+                '{' | '}' | '(' | ')' | ',' | ':' | ';' | '=' | '<' | '>' => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                    tokens.push(c.to_string());
+                }
+                c if c.is_whitespace() => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                _ => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> String {
+        let t = self.tokens[self.pos].clone();
+        self.pos += 1;
+        t
+    }
+
+    fn expect(&mut self, expected: &str) {
+        let got = self.next();
+        assert_eq!(got, expected, "expected `{}`, got `{}`", expected, got);
+    }
+
+    fn parse_type(&mut self) -> FieldType {
+        let name = self.next();
+        match name.as_str() {
+            "Nat0" => FieldType::Nat0,
+            "Int" => FieldType::Int,
+            "Float" => FieldType::Float,
+            "Bool" => FieldType::Bool,
+            "Unit" => FieldType::Unit,
+            "String" => FieldType::Str,
+            "Option" => {
+                self.expect("<");
+                let inner = self.parse_type();
+                self.expect(">");
+                FieldType::Option(Box::new(inner))
+            }
+            "List" => {
+                self.expect("<");
+                let inner = self.parse_type();
+                self.expect(">");
+                FieldType::List(Box::new(inner))
+            }
+            "Tuple" => {
+                self.expect("<");
+                let mut elems = vec![self.parse_type()];
+                while self.peek() == Some(",") {
+                    self.next();
+                    elems.push(self.parse_type());
+                }
+                self.expect(">");
+                FieldType::Tuple(elems)
+            }
+            other => FieldType::Named(other.to_string()),
+        }
+    }
+
+    fn parse_item(&mut self) -> Item {
+        match self.next().as_str() {
+            "record" => {
+                let name = self.next();
+                self.expect("{");
+                let mut fields = Vec::new();
+                while self.peek() != Some("}") {
+                    let field_name = self.next();
+                    self.expect(":");
+                    let ty = self.parse_type();
+                    self.expect(";");
+                    fields.push(Field { name: field_name, ty });
+                }
+                self.expect("}");
+                Item::Record(Record { name, fields })
+            }
+            "variant" => {
+                let name = self.next();
+                self.expect("{");
+                let mut ctors = Vec::new();
+                while self.peek() != Some("}") {
+                    let ctor_name = self.next();
+                    self.expect("=");
+                    let tag: u64 = self.next().parse().expect("variant tag must be a u64");
+                    let mut args = Vec::new();
+                    if self.peek() == Some("(") {
+                        self.next();
+                        while self.peek() != Some(")") {
+                            args.push(self.parse_type());
+                            if self.peek() == Some(",") {
+                                self.next();
+                            }
+                        }
+                        self.expect(")");
+                    }
+                    self.expect(";");
+                    ctors.push((ctor_name, tag, args));
+                }
+                self.expect("}");
+                Item::Variant(Variant { name, ctors })
+            }
+            other => panic!("expected `record` or `variant`, got `{}`", other),
+        }
+    }
+
+    fn parse_items(&mut self) -> Vec<Item> {
+        let mut items = Vec::new();
+        while self.pos < self.tokens.len() {
+            items.push(self.parse_item());
+        }
+        items
+    }
+}
+
+fn codegen_record(r: &Record, out: &mut String) {
+    out.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+    out.push_str(&format!("pub struct {} {{\n", r.name));
+    for f in &r.fields {
+        out.push_str(&format!("    pub {}: {},\n", f.name, f.ty.rust_type()));
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtSize for {} {{\n", r.name));
+    out.push_str("    fn binprot_size(&self) -> usize {\n        ");
+    if r.fields.is_empty() {
+        out.push('0');
+    } else {
+        let terms: Vec<String> = r
+            .fields
+            .iter()
+            .map(|f| format!("self.{}.binprot_size()", f.name))
+            .collect();
+        out.push_str(&terms.join(" + "));
+    }
+    out.push_str("\n    }\n}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtWrite for {} {{\n", r.name));
+    out.push_str("    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {\n");
+    for f in &r.fields {
+        out.push_str(&format!("        self.{}.binprot_write(w)?;\n", f.name));
+    }
+    out.push_str("        Ok(())\n    }\n}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtRead for {} {{\n", r.name));
+    out.push_str("    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, crate::Error>\n    where\n        Self: Sized,\n    {\n");
+    for f in &r.fields {
+        out.push_str(&format!(
+            "        let {} = <{}>::binprot_read(r)?;\n",
+            f.name,
+            f.ty.rust_type()
+        ));
+    }
+    out.push_str(&format!(
+        "        Ok({} {{ {} }})\n    }}\n}}\n\n",
+        r.name,
+        r.fields
+            .iter()
+            .map(|f| f.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    out.push_str(&format!("impl crate::BinProtShape for {} {{\n", r.name));
+    out.push_str("    fn binprot_shape() -> crate::Shape {\n");
+    let field_shapes: Vec<String> = r
+        .fields
+        .iter()
+        .map(|f| format!("({:?}, {})", f.name, f.ty.shape_expr(&r.name)))
+        .collect();
+    out.push_str(&format!(
+        "        crate::Shape::Record(vec![{}])\n",
+        field_shapes.join(", ")
+    ));
+    out.push_str("    }\n}\n\n");
+}
+
+fn codegen_variant(v: &Variant, out: &mut String) {
+    out.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+    out.push_str(&format!("pub enum {} {{\n", v.name));
+    for (ctor, _tag, args) in &v.ctors {
+        if args.is_empty() {
+            out.push_str(&format!("    {},\n", ctor));
+        } else {
+            let arg_types: Vec<String> = args.iter().map(FieldType::rust_type).collect();
+            out.push_str(&format!("    {}({}),\n", ctor, arg_types.join(", ")));
+        }
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtSize for {} {{\n", v.name));
+    out.push_str("    fn binprot_size(&self) -> usize {\n        match self {\n");
+    for (ctor, tag, args) in &v.ctors {
+        let bind_names: Vec<String> = (0..args.len()).map(|i| format!("a{}", i)).collect();
+        let pattern = if bind_names.is_empty() {
+            format!("{}::{}", v.name, ctor)
+        } else {
+            format!("{}::{}({})", v.name, ctor, bind_names.join(", "))
+        };
+        let mut expr = format!("crate::Nat0({}).binprot_size()", tag);
+        for bind in &bind_names {
+            expr.push_str(&format!(" + {}.binprot_size()", bind));
+        }
+        out.push_str(&format!("            {} => {},\n", pattern, expr));
+    }
+    out.push_str("        }\n    }\n}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtWrite for {} {{\n", v.name));
+    out.push_str("    fn binprot_write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {\n        match self {\n");
+    for (ctor, tag, args) in &v.ctors {
+        let bind_names: Vec<String> = (0..args.len()).map(|i| format!("a{}", i)).collect();
+        let pattern = if bind_names.is_empty() {
+            format!("{}::{}", v.name, ctor)
+        } else {
+            format!("{}::{}({})", v.name, ctor, bind_names.join(", "))
+        };
+        out.push_str(&format!("            {} => {{\n", pattern));
+        out.push_str(&format!("                crate::Nat0({}).binprot_write(w)?;\n", tag));
+        for bind in &bind_names {
+            out.push_str(&format!("                {}.binprot_write(w)?;\n", bind));
+        }
+        out.push_str("                Ok(())\n            }\n");
+    }
+    out.push_str("        }\n    }\n}\n\n");
+
+    out.push_str(&format!("impl crate::BinProtRead for {} {{\n", v.name));
+    out.push_str("    fn binprot_read<R: std::io::Read + ?Sized>(r: &mut R) -> Result<Self, crate::Error>\n    where\n        Self: Sized,\n    {\n");
+    out.push_str("        let tag = crate::Nat0::binprot_read(r)?.0;\n        match tag {\n");
+    for (ctor, tag, args) in &v.ctors {
+        if args.is_empty() {
+            out.push_str(&format!("            {} => Ok({}::{}),\n", tag, v.name, ctor));
+        } else {
+            let reads: Vec<String> = args
+                .iter()
+                .map(|a| format!("<{}>::binprot_read(r)?", a.rust_type()))
+                .collect();
+            out.push_str(&format!(
+                "            {} => Ok({}::{}({})),\n",
+                tag,
+                v.name,
+                ctor,
+                reads.join(", ")
+            ));
+        }
+    }
+    out.push_str(
+        "            other => Err(crate::Error::UnknownVariantTag(other)),\n        }\n    }\n}\n\n",
+    );
+
+    out.push_str(&format!("impl crate::BinProtShape for {} {{\n", v.name));
+    out.push_str("    fn binprot_shape() -> crate::Shape {\n");
+    let ctor_shapes: Vec<String> = v
+        .ctors
+        .iter()
+        .map(|(ctor, _tag, args)| {
+            let arg_exprs: Vec<String> =
+                args.iter().map(|a| a.shape_expr(&v.name)).collect();
+            format!("({:?}, vec![{}])", ctor, arg_exprs.join(", "))
+        })
+        .collect();
+    out.push_str(&format!(
+        "        crate::Shape::Variant(vec![{}])\n",
+        ctor_shapes.join(", ")
+    ));
+    out.push_str("    }\n}\n\n");
+}
+
+fn main() {
+    let schema_dir = Path::new("schema");
+    println!("cargo:rerun-if-changed=schema");
+
+    let mut out = String::new();
+    out.push_str("// @generated by build.rs from schema/*.types. Do not edit by hand.\n");
+    out.push_str("use crate::{BinProtRead, BinProtShape, BinProtSize, BinProtWrite};\n\n");
+
+    let mut schema_files: Vec<_> = fs::read_dir(schema_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("types"))
+                .collect()
+        })
+        .unwrap_or_default();
+    schema_files.sort();
+
+    for path in schema_files {
+        let src = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e));
+        let tokens = tokenize(&src);
+        let mut parser = Parser { tokens, pos: 0 };
+        for item in parser.parse_items() {
+            match item {
+                Item::Record(r) => codegen_record(&r, &mut out),
+                Item::Variant(v) => codegen_variant(&v, &mut out),
+            }
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    fs::write(Path::new(&out_dir).join("generated.rs"), out).expect("failed to write generated.rs");
+}
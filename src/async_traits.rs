@@ -0,0 +1,123 @@
+//! Async framing for the size-prefixed binprot protocol (gated behind the `async`
+//! feature), built on `tokio_util`'s [`Decoder`]/[`Encoder`] traits so a
+//! `B: BinProtRead + BinProtWrite` can be driven directly off a `tokio::io::AsyncRead`/
+//! `AsyncWrite` stream via `tokio_util::codec::Framed`.
+//!
+//! A frame is buffered in full — 8-byte little-endian length prefix followed by that
+//! many bytes — before decoding runs, so `B::binprot_read` always sees a complete value
+//! and never has to deal with a short read itself.
+
+use crate::{BinProtRead, BinProtWrite, Error};
+use bytes::{Buf, BufMut, BytesMut};
+use std::convert::TryInto;
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+const LENGTH_PREFIX_BYTES: usize = 8;
+
+/// Default cap on a single frame's declared length, used by [`BinProtCodec::new`].
+/// Without a cap, a peer could send a length prefix claiming an enormous frame and make
+/// us grow the read buffer to match before any of the frame's bytes have even arrived.
+const DEFAULT_MAX_FRAME_LEN: u64 = 64 * 1024 * 1024;
+
+/// A `Decoder`/`Encoder` pair for any `B: BinProtRead + BinProtWrite`, framing each
+/// value with the same 8-byte little-endian length prefix used by
+/// [`crate::binprot_write_with_size`].
+pub struct BinProtCodec<B> {
+    max_frame_len: u64,
+    _marker: PhantomData<B>,
+}
+
+impl<B> BinProtCodec<B> {
+    /// Creates a codec that rejects any frame whose declared length exceeds
+    /// [`DEFAULT_MAX_FRAME_LEN`]. Use [`BinProtCodec::with_max_frame_len`] to override it.
+    pub fn new() -> Self {
+        Self::with_max_frame_len(DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a codec that rejects any frame whose declared length exceeds `max_frame_len`.
+    pub fn with_max_frame_len(max_frame_len: u64) -> Self {
+        BinProtCodec {
+            max_frame_len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<B> Default for BinProtCodec<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: BinProtRead> Decoder for BinProtCodec<B> {
+    type Item = B;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < LENGTH_PREFIX_BYTES {
+            return Ok(None);
+        }
+        let len = u64::from_le_bytes(src[..LENGTH_PREFIX_BYTES].try_into().unwrap());
+        if len > self.max_frame_len {
+            return Err(Error::FrameTooLarge {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+        let len = len as usize;
+        if src.len() < LENGTH_PREFIX_BYTES + len {
+            src.reserve(LENGTH_PREFIX_BYTES + len - src.len());
+            return Ok(None);
+        }
+        src.advance(LENGTH_PREFIX_BYTES);
+        let frame = src.split_to(len);
+        let value = B::binprot_read(&mut frame.as_ref())?;
+        Ok(Some(value))
+    }
+}
+
+impl<B: BinProtWrite> Encoder<B> for BinProtCodec<B> {
+    type Error = Error;
+
+    fn encode(&mut self, item: B, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let len = item.binprot_size();
+        dst.reserve(LENGTH_PREFIX_BYTES + len);
+        dst.put_u64_le(len as u64);
+        item.binprot_write(&mut dst.writer())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_value_through_encode_and_decode() {
+        let mut codec = BinProtCodec::<i64>::new();
+        let mut buf = BytesMut::new();
+        codec.encode(42i64, &mut buf).unwrap();
+        let value = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn waits_for_more_bytes_on_an_incomplete_frame() {
+        let mut codec = BinProtCodec::<i64>::new();
+        let mut full = BytesMut::new();
+        codec.encode(42i64, &mut full).unwrap();
+        let mut partial = BytesMut::from(&full[..full.len() - 1]);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_a_declared_length_over_the_configured_maximum() {
+        let mut codec = BinProtCodec::<i64>::with_max_frame_len(4);
+        let mut buf = BytesMut::new();
+        buf.put_u64_le(5);
+        buf.extend_from_slice(&[0u8; 5]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::FrameTooLarge { len: 5, max: 4 }));
+    }
+}
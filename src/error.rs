@@ -0,0 +1,91 @@
+use std::fmt;
+
+/// Errors that can occur while decoding a binprot-encoded value.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Utf8(std::str::Utf8Error),
+    UnexpectedValueForUnit(u8),
+    UnexpectedValueForBool(u8),
+    UnexpectedValueForOption(u8),
+    SameKeyAppearsTwiceInMap,
+    /// A variable-length int/Nat0 encoding started with a tag byte that isn't one of the
+    /// recognized width markers (0xff/0xfe/0xfd/0xfc for int, 0xfe/0xfd/0xfc for Nat0).
+    InvalidIntTag(u8),
+    /// A size-prefixed frame still had unconsumed bytes left after decoding the value,
+    /// meaning the decoder read less than the frame's declared length.
+    TrailingBytes { remaining: u64 },
+    /// The input ended early: a stream ended before the declared frame length was fully
+    /// consumed, or a `BinProtReader`'s slice ran out in the middle of a value.
+    UnexpectedEof,
+    /// A sequence, string or map's declared length exceeded the caller's `ReadLimits`.
+    LengthLimitExceeded,
+    /// A framed message's declared length exceeded the codec's configured maximum,
+    /// before any attempt was made to buffer or decode it.
+    FrameTooLarge { len: u64, max: u64 },
+    /// A generated variant's tag didn't match any constructor declared for it in its
+    /// `schema/*.types` source.
+    UnknownVariantTag(u64),
+    /// A size-prefixed frame's declared length, read as a signed `i64`, was negative.
+    InvalidFrameLength(i64),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Utf8(e) => write!(f, "utf8 error: {}", e),
+            Error::UnexpectedValueForUnit(c) => {
+                write!(f, "unexpected value for unit: {}", c)
+            }
+            Error::UnexpectedValueForBool(c) => {
+                write!(f, "unexpected value for bool: {}", c)
+            }
+            Error::UnexpectedValueForOption(c) => {
+                write!(f, "unexpected value for option: {}", c)
+            }
+            Error::SameKeyAppearsTwiceInMap => write!(f, "same key appears twice in map"),
+            Error::InvalidIntTag(c) => write!(f, "invalid int/Nat0 tag byte: {}", c),
+            Error::TrailingBytes { remaining } => {
+                write!(f, "{} trailing byte(s) left in size-prefixed frame", remaining)
+            }
+            Error::UnexpectedEof => {
+                write!(f, "input ended before the expected number of bytes was read")
+            }
+            Error::LengthLimitExceeded => {
+                write!(f, "declared length exceeded the configured decoding limits")
+            }
+            Error::FrameTooLarge { len, max } => {
+                write!(f, "frame length {} exceeds configured maximum {}", len, max)
+            }
+            Error::UnknownVariantTag(tag) => {
+                write!(f, "unknown variant tag: {}", tag)
+            }
+            Error::InvalidFrameLength(len) => {
+                write!(f, "frame length prefix was negative: {}", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(e: std::str::Utf8Error) -> Self {
+        Error::Utf8(e)
+    }
+}
@@ -0,0 +1,177 @@
+//! Fixed-width primitives distinct from `i64`'s variable-length `int` encoding and
+//! `Nat0`. OCaml bin_prot's `Int32.t`/`Int64.t` are always written as raw 4/8-byte
+//! little-endian values with no tag byte, and `char` as a single raw byte — using the
+//! polymorphic variable-length scheme for a field the OCaml side declared `int32`,
+//! `int64` or `char` produces wire-incompatible bytes. These wrapper types (and the
+//! `char` impl) give that fixed-width path explicitly, alongside the existing
+//! variable-length `i64`/`Nat0`.
+
+use crate::{BinProtRead, BinProtShape, BinProtSize, BinProtWrite, Error, Shape};
+use std::io::{Read, Write};
+
+/// A fixed 4-byte little-endian `Int32.t`, matching `Bin_prot.Write.bin_write_int32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Int32(pub i32);
+
+impl BinProtSize for Int32 {
+    fn binprot_size(&self) -> usize {
+        4
+    }
+}
+
+impl BinProtWrite for Int32 {
+    fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(&self.0.to_le_bytes())
+    }
+}
+
+impl BinProtRead for Int32 {
+    fn binprot_read<R: Read + ?Sized>(r: &mut R) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut buf = [0u8; 4];
+        r.read_exact(&mut buf)?;
+        Ok(Int32(i32::from_le_bytes(buf)))
+    }
+}
+
+impl BinProtShape for Int32 {
+    fn binprot_shape() -> Shape {
+        Shape::FixedInt32
+    }
+}
+
+/// A fixed 8-byte little-endian `Int64.t`, matching `Bin_prot.Write.bin_write_int64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Int64(pub i64);
+
+impl BinProtSize for Int64 {
+    fn binprot_size(&self) -> usize {
+        8
+    }
+}
+
+impl BinProtWrite for Int64 {
+    fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(&self.0.to_le_bytes())
+    }
+}
+
+impl BinProtRead for Int64 {
+    fn binprot_read<R: Read + ?Sized>(r: &mut R) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut buf = [0u8; 8];
+        r.read_exact(&mut buf)?;
+        Ok(Int64(i64::from_le_bytes(buf)))
+    }
+}
+
+impl BinProtShape for Int64 {
+    fn binprot_shape() -> Shape {
+        Shape::FixedInt64
+    }
+}
+
+impl BinProtSize for char {
+    fn binprot_size(&self) -> usize {
+        1
+    }
+}
+
+impl BinProtWrite for char {
+    fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let c = *self as u32;
+        if c > 0xff {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "char is out of range for binprot's single-byte char encoding",
+            ));
+        }
+        w.write_all(&[c as u8])
+    }
+}
+
+impl BinProtRead for char {
+    fn binprot_read<R: Read + ?Sized>(r: &mut R) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        let mut buf = [0u8; 1];
+        r.read_exact(&mut buf)?;
+        Ok(char::from(buf[0]))
+    }
+}
+
+impl BinProtShape for char {
+    fn binprot_shape() -> Shape {
+        Shape::Char
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip_int32(v: i32, expected_bytes: [u8; 4]) {
+        let mut buf = Vec::new();
+        Int32(v).binprot_write(&mut buf).unwrap();
+        assert_eq!(buf, expected_bytes);
+        let decoded = Int32::binprot_read(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, Int32(v));
+    }
+
+    fn roundtrip_int64(v: i64, expected_bytes: [u8; 8]) {
+        let mut buf = Vec::new();
+        Int64(v).binprot_write(&mut buf).unwrap();
+        assert_eq!(buf, expected_bytes);
+        let decoded = Int64::binprot_read(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, Int64(v));
+    }
+
+    #[test]
+    fn int32_boundary_values() {
+        roundtrip_int32(0x7f, [0x7f, 0, 0, 0]);
+        roundtrip_int32(0x80, [0x80, 0, 0, 0]);
+        roundtrip_int32(0xffff, [0xff, 0xff, 0, 0]);
+        roundtrip_int32(-1, [0xff, 0xff, 0xff, 0xff]);
+        roundtrip_int32(i32::MIN, [0x00, 0x00, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn int64_boundary_values() {
+        roundtrip_int64(0x7f, [0x7f, 0, 0, 0, 0, 0, 0, 0]);
+        roundtrip_int64(0x80, [0x80, 0, 0, 0, 0, 0, 0, 0]);
+        roundtrip_int64(0xffff, [0xff, 0xff, 0, 0, 0, 0, 0, 0]);
+        roundtrip_int64(-1, [0xff; 8]);
+        roundtrip_int64(i64::MIN, [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn char_roundtrips_as_single_byte() {
+        let mut buf = Vec::new();
+        'a'.binprot_write(&mut buf).unwrap();
+        assert_eq!(buf, [b'a']);
+        assert_eq!(char::binprot_read(&mut &buf[..]).unwrap(), 'a');
+    }
+
+    #[test]
+    fn char_out_of_byte_range_is_rejected() {
+        let mut buf = Vec::new();
+        assert!('\u{100}'.binprot_write(&mut buf).is_err());
+    }
+
+    #[test]
+    fn fixed_width_differs_from_variable_length_int_encoding() {
+        // i64's variable-length `int` encoding tags 0xffff with 0xfd (CODE_INT32) + a
+        // 4-byte i32, since 0xffff exceeds i16 range; Int64's fixed-width encoding has
+        // no tag at all.
+        let mut variable = Vec::new();
+        0xffffi64.binprot_write(&mut variable).unwrap();
+        let mut fixed = Vec::new();
+        Int64(0xffff).binprot_write(&mut fixed).unwrap();
+        assert_ne!(variable, fixed);
+    }
+}
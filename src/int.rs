@@ -0,0 +1,106 @@
+use crate::Error;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Read, Write};
+
+pub(crate) const CODE_INT16: u8 = 0xfe;
+pub(crate) const CODE_INT32: u8 = 0xfd;
+pub(crate) const CODE_INT64: u8 = 0xfc;
+pub(crate) const CODE_NEG_INT8: u8 = 0xff;
+
+/// Writes a non-negative length/count using bin_prot's variable-width `Nat0` encoding:
+/// values below 0x80 are written as a single byte, larger values get a tag byte
+/// (0xfe/0xfd/0xfc) followed by a little-endian 16/32/64-bit width.
+pub fn write_nat0<W: Write>(w: &mut W, v: u64) -> std::io::Result<()> {
+    if v < 0x80 {
+        w.write_u8(v as u8)
+    } else if v < 0x1_0000 {
+        w.write_u8(CODE_INT16)?;
+        w.write_u16::<LittleEndian>(v as u16)
+    } else if v < 0x1_0000_0000 {
+        w.write_u8(CODE_INT32)?;
+        w.write_u32::<LittleEndian>(v as u32)
+    } else {
+        w.write_u8(CODE_INT64)?;
+        w.write_u64::<LittleEndian>(v)
+    }
+}
+
+pub fn read_nat0<R: Read + ?Sized>(r: &mut R) -> Result<u64, Error> {
+    let c = r.read_u8()?;
+    let v = if c < 0x80 {
+        c as u64
+    } else {
+        match c {
+            CODE_INT16 => r.read_u16::<LittleEndian>()? as u64,
+            CODE_INT32 => r.read_u32::<LittleEndian>()? as u64,
+            CODE_INT64 => r.read_u64::<LittleEndian>()?,
+            other => return Err(Error::InvalidIntTag(other)),
+        }
+    };
+    Ok(v)
+}
+
+/// Number of bytes `write_nat0` would emit for `v`.
+pub fn nat0_size(v: u64) -> usize {
+    if v < 0x80 {
+        1
+    } else if v < 0x1_0000 {
+        3
+    } else if v < 0x1_0000_0000 {
+        5
+    } else {
+        9
+    }
+}
+
+/// Writes a signed integer using bin_prot's variable-width `int` encoding: values in
+/// `0..0x80` are written as a single byte, everything else gets a tag byte
+/// (0xff/0xfe/0xfd/0xfc) followed by the smallest signed width that fits.
+pub fn write_i64<W: Write>(w: &mut W, v: i64) -> std::io::Result<()> {
+    if (0..0x80).contains(&v) {
+        w.write_u8(v as u8)
+    } else if (-0x80..0x80).contains(&v) {
+        w.write_u8(CODE_NEG_INT8)?;
+        w.write_i8(v as i8)
+    } else if (-0x8000..0x8000).contains(&v) {
+        w.write_u8(CODE_INT16)?;
+        w.write_i16::<LittleEndian>(v as i16)
+    } else if (-0x8000_0000..0x8000_0000).contains(&v) {
+        w.write_u8(CODE_INT32)?;
+        w.write_i32::<LittleEndian>(v as i32)
+    } else {
+        w.write_u8(CODE_INT64)?;
+        w.write_i64::<LittleEndian>(v)
+    }
+}
+
+pub fn read_signed<R: Read + ?Sized>(r: &mut R) -> Result<i64, Error> {
+    let c = r.read_u8()?;
+    let v = if c < 0x80 {
+        c as i64
+    } else {
+        match c {
+            CODE_NEG_INT8 => r.read_i8()? as i64,
+            CODE_INT16 => r.read_i16::<LittleEndian>()? as i64,
+            CODE_INT32 => r.read_i32::<LittleEndian>()? as i64,
+            CODE_INT64 => r.read_i64::<LittleEndian>()?,
+            other => return Err(Error::InvalidIntTag(other)),
+        }
+    };
+    Ok(v)
+}
+
+/// Number of bytes `write_i64` would emit for `v`.
+pub fn i64_size(v: i64) -> usize {
+    if (0..0x80).contains(&v) {
+        1
+    } else if (-0x80..0x80).contains(&v) {
+        2
+    } else if (-0x8000..0x8000).contains(&v) {
+        3
+    } else if (-0x8000_0000..0x8000_0000).contains(&v) {
+        5
+    } else {
+        9
+    }
+}
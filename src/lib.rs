@@ -1,13 +1,29 @@
 #[cfg(feature = "async")]
 mod async_traits;
+#[cfg(feature = "async")]
+pub use crate::async_traits::BinProtCodec;
 
 mod error;
+mod fixed;
 mod int;
+mod limits;
+mod reader;
+mod shape;
 mod traits;
 
 pub use crate::error::Error;
+pub use crate::fixed::{Int32, Int64};
+pub use crate::limits::ReadLimits;
+pub use crate::reader::{BinProtReadFast, BinProtReader};
+pub use crate::shape::{shape_digest, BinProtShape, Shape};
 pub use crate::traits::{BinProtRead, BinProtSize, BinProtWrite};
 
+/// Types generated at build time from `schema/*.types` by `build.rs` — see that file
+/// for the schema grammar.
+pub mod generated {
+    include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+}
+
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::hash::Hash;
 use std::io::{Read, Write};
@@ -20,40 +36,112 @@ pub fn binprot_write_with_size<W: Write, B: BinProtWrite>(b: &B, w: &mut W) -> s
     b.binprot_write(w)
 }
 
-/// This also uses the "size-prefixed binary protocol".
+/// Reads the 8-byte little-endian frame length prefix shared by
+/// `binprot_read_with_size`/`binprot_skip_with_size`, rejecting a negative value
+/// instead of letting it wrap to a huge `u64` when cast.
+fn read_frame_len<R: Read>(r: &mut R) -> Result<u64, Error> {
+    let len = r.read_i64::<byteorder::LittleEndian>()?;
+    if len < 0 {
+        return Err(Error::InvalidFrameLength(len));
+    }
+    Ok(len as u64)
+}
+
+/// This also uses the "size-prefixed binary protocol". The declared length is used to
+/// bound the read: `B::binprot_read` is run against a reader limited to exactly `len`
+/// bytes, and it is an error for it to consume anything less than that.
 pub fn binprot_read_with_size<R: Read, B: BinProtRead>(r: &mut R) -> Result<B, Error> {
-    // TODO: use the length value to avoid reading more that the specified number of bytes.
-    let _len = r.read_i64::<byteorder::LittleEndian>()?;
-    B::binprot_read(r)
+    let len = read_frame_len(r)?;
+    let mut limited = r.take(len);
+    let value = B::binprot_read(&mut limited)?;
+    if limited.limit() != 0 {
+        return Err(Error::TrailingBytes {
+            remaining: limited.limit(),
+        });
+    }
+    Ok(value)
+}
+
+/// Skips over a size-prefixed value without decoding it, by reading and discarding
+/// exactly the number of bytes the frame declares. Useful when demultiplexing a stream
+/// that carries message types this side doesn't know how to decode.
+pub fn binprot_skip_with_size<R: Read>(r: &mut R) -> Result<(), Error> {
+    let len = read_frame_len(r)?;
+    let mut limited = r.take(len);
+    let copied = std::io::copy(&mut limited, &mut std::io::sink())?;
+    if copied != len {
+        return Err(Error::UnexpectedEof);
+    }
+    Ok(())
+}
+
+/// Decodes a value with `limits` enforced against every `Vec`/`String`/`BTreeMap`/
+/// `HashMap` length read during the decode, protecting against hostile or corrupt
+/// length prefixes that would otherwise drive an allocation or loop bound directly.
+pub fn binprot_read_with_limits<R: Read, B: BinProtRead>(
+    r: &mut R,
+    limits: ReadLimits,
+) -> Result<B, Error> {
+    limits::with_limits(limits, || B::binprot_read(r))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub struct Nat0(pub u64);
 
+impl BinProtSize for Nat0 {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.0)
+    }
+}
+
 impl BinProtWrite for Nat0 {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         int::write_nat0(w, self.0)
     }
 }
 
+impl BinProtSize for i64 {
+    fn binprot_size(&self) -> usize {
+        int::i64_size(*self)
+    }
+}
+
 impl BinProtWrite for i64 {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         int::write_i64(w, *self)
     }
 }
 
+impl BinProtSize for f64 {
+    fn binprot_size(&self) -> usize {
+        8
+    }
+}
+
 impl BinProtWrite for f64 {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         w.write_all(&self.to_le_bytes())
     }
 }
 
+impl BinProtSize for () {
+    fn binprot_size(&self) -> usize {
+        1
+    }
+}
+
 impl BinProtWrite for () {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         w.write_all(&[0u8])
     }
 }
 
+impl BinProtSize for bool {
+    fn binprot_size(&self) -> usize {
+        1
+    }
+}
+
 impl BinProtWrite for bool {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         let b = if *self { 1 } else { 0 };
@@ -61,9 +149,18 @@ impl BinProtWrite for bool {
     }
 }
 
+impl<T: BinProtSize> BinProtSize for Option<T> {
+    fn binprot_size(&self) -> usize {
+        match self {
+            None => 1,
+            Some(v) => 1 + v.binprot_size(),
+        }
+    }
+}
+
 impl<T: BinProtWrite> BinProtWrite for Option<T> {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
-        match &*self {
+        match self {
             None => w.write_all(&[0u8]),
             Some(v) => {
                 w.write_all(&[1u8])?;
@@ -73,6 +170,12 @@ impl<T: BinProtWrite> BinProtWrite for Option<T> {
     }
 }
 
+impl<T: BinProtSize> BinProtSize for Vec<T> {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64) + self.iter().map(BinProtSize::binprot_size).sum::<usize>()
+    }
+}
+
 impl<T: BinProtWrite> BinProtWrite for Vec<T> {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         int::write_nat0(w, self.len() as u64)?;
@@ -83,6 +186,12 @@ impl<T: BinProtWrite> BinProtWrite for Vec<T> {
     }
 }
 
+impl<T: BinProtSize> BinProtSize for &[T] {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64) + self.iter().map(BinProtSize::binprot_size).sum::<usize>()
+    }
+}
+
 impl<T: BinProtWrite> BinProtWrite for &[T] {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         int::write_nat0(w, self.len() as u64)?;
@@ -93,11 +202,23 @@ impl<T: BinProtWrite> BinProtWrite for &[T] {
     }
 }
 
+impl BinProtSize for String {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64) + self.len()
+    }
+}
+
 impl BinProtWrite for String {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         let bytes = self.as_bytes();
         int::write_nat0(w, bytes.len() as u64)?;
-        w.write_all(&bytes)
+        w.write_all(bytes)
+    }
+}
+
+impl BinProtSize for &str {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64) + self.len()
     }
 }
 
@@ -105,7 +226,17 @@ impl BinProtWrite for &str {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         let bytes = self.as_bytes();
         int::write_nat0(w, bytes.len() as u64)?;
-        w.write_all(&bytes)
+        w.write_all(bytes)
+    }
+}
+
+impl<K: BinProtSize, V: BinProtSize> BinProtSize for std::collections::BTreeMap<K, V> {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64)
+            + self
+                .iter()
+                .map(|(k, v)| k.binprot_size() + v.binprot_size())
+                .sum::<usize>()
     }
 }
 
@@ -121,6 +252,16 @@ impl<K: BinProtWrite, V: BinProtWrite> BinProtWrite for std::collections::BTreeM
     }
 }
 
+impl<K: BinProtSize, V: BinProtSize> BinProtSize for std::collections::HashMap<K, V> {
+    fn binprot_size(&self) -> usize {
+        int::nat0_size(self.len() as u64)
+            + self
+                .iter()
+                .map(|(k, v)| k.binprot_size() + v.binprot_size())
+                .sum::<usize>()
+    }
+}
+
 impl<K: BinProtWrite, V: BinProtWrite> BinProtWrite for std::collections::HashMap<K, V> {
     // The order is unspecified by the protocol
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
@@ -135,6 +276,15 @@ impl<K: BinProtWrite, V: BinProtWrite> BinProtWrite for std::collections::HashMa
 
 macro_rules! tuple_impls {
     ( $( $name:ident )+ ) => {
+        impl<$($name: BinProtSize),+> BinProtSize for ($($name,)+)
+        {
+            #[allow(non_snake_case)]
+            fn binprot_size(&self) -> usize {
+                let ($($name,)+) = self;
+                [$($name.binprot_size()),+].iter().sum::<usize>()
+            }
+        }
+
         impl<$($name: BinProtWrite),+> BinProtWrite for ($($name,)+)
         {
             #[allow(non_snake_case)]
@@ -252,6 +402,7 @@ impl<T: BinProtRead> BinProtRead for Vec<T> {
         Self: Sized,
     {
         let len = int::read_nat0(r)?;
+        limits::charge_seq_len(len)?;
         let mut v: Vec<T> = Vec::new();
         for _i in 0..len {
             let item = T::binprot_read(r)?;
@@ -267,6 +418,7 @@ impl<K: BinProtRead + Ord, V: BinProtRead> BinProtRead for std::collections::BTr
         Self: Sized,
     {
         let len = int::read_nat0(r)?;
+        limits::charge_seq_len(len)?;
         let mut res = std::collections::BTreeMap::new();
         for _i in 0..len {
             let k = K::binprot_read(r)?;
@@ -285,6 +437,7 @@ impl<K: BinProtRead + Hash + Eq, V: BinProtRead> BinProtRead for std::collection
         Self: Sized,
     {
         let len = int::read_nat0(r)?;
+        limits::charge_seq_len(len)?;
         let mut res = std::collections::HashMap::new();
         for _i in 0..len {
             let k = K::binprot_read(r)?;
@@ -303,6 +456,7 @@ impl BinProtRead for String {
         Self: Sized,
     {
         let len = int::read_nat0(r)?;
+        limits::charge_string_bytes(len)?;
         let mut buf: Vec<u8> = vec![0u8; len as usize];
         r.read_exact(&mut buf)?;
         let str = std::str::from_utf8(&buf)?;
@@ -313,6 +467,13 @@ impl BinProtRead for String {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WithLen<T>(pub T);
 
+impl<T: BinProtSize> BinProtSize for WithLen<T> {
+    fn binprot_size(&self) -> usize {
+        let len = self.0.binprot_size();
+        int::nat0_size(len as u64) + len
+    }
+}
+
 impl<T: BinProtWrite + BinProtSize> BinProtWrite for WithLen<T> {
     fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         let len = self.0.binprot_size();
@@ -326,9 +487,177 @@ impl<T: BinProtRead> BinProtRead for WithLen<T> {
     where
         Self: Sized,
     {
-        // TODO: stop reading past this length
-        let _len = int::read_nat0(r)?;
-        let t = T::binprot_read(r)?;
+        let len = int::read_nat0(r)?;
+        let mut limited = r.take(len);
+        let t = T::binprot_read(&mut limited)?;
+        if limited.limit() != 0 {
+            return Err(Error::TrailingBytes {
+                remaining: limited.limit(),
+            });
+        }
         Ok(WithLen(t))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_size_prefix() {
+        let mut buf = Vec::new();
+        binprot_write_with_size(&42i64, &mut buf).unwrap();
+        let value: i64 = binprot_read_with_size(&mut buf.as_slice()).unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn rejects_trailing_bytes_left_after_decoding() {
+        // Declare a frame of 2 bytes but only consume 1 (a single-byte Nat0 encoding).
+        let mut buf = Vec::new();
+        buf.write_i64::<LittleEndian>(2).unwrap();
+        buf.push(5u8);
+        buf.push(0u8);
+        let err = binprot_read_with_size::<_, Nat0>(&mut buf.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::TrailingBytes { remaining: 1 }));
+    }
+
+    #[test]
+    fn skip_with_size_discards_exactly_the_declared_length() {
+        let mut buf = Vec::new();
+        binprot_write_with_size(&"hello".to_string(), &mut buf).unwrap();
+        buf.extend_from_slice(b"trailing data");
+        let mut cursor = buf.as_slice();
+        binprot_skip_with_size(&mut cursor).unwrap();
+        assert_eq!(cursor, b"trailing data");
+    }
+
+    #[test]
+    fn skip_with_size_errors_on_truncated_frame() {
+        let mut buf = Vec::new();
+        buf.write_i64::<LittleEndian>(10).unwrap();
+        buf.extend_from_slice(&[0u8; 3]);
+        let err = binprot_skip_with_size(&mut buf.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof));
+    }
+
+    #[test]
+    fn read_with_size_rejects_a_negative_length_prefix() {
+        let mut buf = Vec::new();
+        buf.write_i64::<LittleEndian>(-1).unwrap();
+        let err = binprot_read_with_size::<_, Nat0>(&mut buf.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::InvalidFrameLength(-1)));
+    }
+
+    #[test]
+    fn skip_with_size_rejects_a_negative_length_prefix() {
+        let mut buf = Vec::new();
+        buf.write_i64::<LittleEndian>(-1).unwrap();
+        let err = binprot_skip_with_size(&mut buf.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::InvalidFrameLength(-1)));
+    }
+
+    #[test]
+    fn read_with_limits_enforces_the_seq_len_cap_on_a_vec() {
+        let mut buf = Vec::new();
+        vec![1i64, 2, 3].binprot_write(&mut buf).unwrap();
+        let limits = ReadLimits {
+            max_seq_len: 2,
+            ..ReadLimits::default()
+        };
+        let err = binprot_read_with_limits::<_, Vec<i64>>(&mut buf.as_slice(), limits)
+            .unwrap_err();
+        assert!(matches!(err, Error::LengthLimitExceeded));
+    }
+
+    #[test]
+    fn read_with_limits_enforces_the_string_bytes_cap() {
+        let mut buf = Vec::new();
+        "hello world".to_string().binprot_write(&mut buf).unwrap();
+        let limits = ReadLimits {
+            max_string_bytes: 4,
+            ..ReadLimits::default()
+        };
+        let err = binprot_read_with_limits::<_, String>(&mut buf.as_slice(), limits)
+            .unwrap_err();
+        assert!(matches!(err, Error::LengthLimitExceeded));
+    }
+
+    #[test]
+    fn read_with_limits_enforces_the_seq_len_cap_on_a_map() {
+        let mut buf = Vec::new();
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(1i64, "a".to_string());
+        map.insert(2i64, "b".to_string());
+        map.binprot_write(&mut buf).unwrap();
+        let limits = ReadLimits {
+            max_seq_len: 1,
+            ..ReadLimits::default()
+        };
+        let err = binprot_read_with_limits::<_, std::collections::BTreeMap<i64, String>>(
+            &mut buf.as_slice(),
+            limits,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::LengthLimitExceeded));
+    }
+
+    #[test]
+    fn read_with_limits_allows_a_value_within_the_caps() {
+        let mut buf = Vec::new();
+        vec![1i64, 2, 3].binprot_write(&mut buf).unwrap();
+        let value: Vec<i64> =
+            binprot_read_with_limits(&mut buf.as_slice(), ReadLimits::default()).unwrap();
+        assert_eq!(value, vec![1, 2, 3]);
+    }
+}
+
+#[cfg(test)]
+mod generated_tests {
+    use super::*;
+    use crate::generated::{Figure, Labeled, Point};
+
+    #[test]
+    fn a_record_round_trips_through_write_and_read() {
+        let value = Point { x: -3, y: 7 };
+        let mut buf = Vec::new();
+        value.binprot_write(&mut buf).unwrap();
+        let decoded = Point::binprot_read(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn a_variant_round_trips_through_write_and_read() {
+        let value = Figure::Polygon(vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }]);
+        let mut buf = Vec::new();
+        value.binprot_write(&mut buf).unwrap();
+        let decoded = Figure::binprot_read(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn an_unknown_variant_tag_is_rejected() {
+        // Tag 99 isn't one of `Figure`'s declared constructors.
+        let mut buf = Vec::new();
+        Nat0(99).binprot_write(&mut buf).unwrap();
+        let err = Figure::binprot_read(&mut buf.as_slice()).unwrap_err();
+        assert!(matches!(err, Error::UnknownVariantTag(99)));
+    }
+
+    #[test]
+    fn a_generated_record_and_variant_have_distinct_shapes() {
+        assert_ne!(shape_digest::<Point>(), shape_digest::<Figure>());
+    }
+
+    #[test]
+    fn a_record_field_declared_as_a_schema_tuple_round_trips() {
+        let value = Labeled {
+            name: "diagonal".to_string(),
+            bounds: (Point { x: 0, y: 0 }, Point { x: 10, y: 10 }),
+        };
+        let mut buf = Vec::new();
+        value.binprot_write(&mut buf).unwrap();
+        let decoded = Labeled::binprot_read(&mut buf.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::Error;
+use std::cell::Cell;
+
+/// Caps placed on an in-progress decode to protect against hostile or corrupt length
+/// prefixes. `max_total_bytes` is a single budget shared across the whole decode: every
+/// sequence, string and map length charged against it is subtracted as it is read, so
+/// deeply nested structures can't each claim the full cap individually.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadLimits {
+    pub max_seq_len: u64,
+    pub max_string_bytes: u64,
+    pub max_total_bytes: u64,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        ReadLimits {
+            max_seq_len: u64::MAX,
+            max_string_bytes: u64::MAX,
+            max_total_bytes: u64::MAX,
+        }
+    }
+}
+
+thread_local! {
+    static BUDGET: Cell<Option<ReadLimits>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with `limits` enforced against every length-prefixed `Vec`/`String`/
+/// `BTreeMap`/`HashMap` read for its duration. Calls may nest: entering saves whatever
+/// scope (if any) was active and restores it on exit, so an inner call's limits apply
+/// only for its own dynamic extent, after which the enclosing scope's limits (or no
+/// limits, if there was no enclosing call) take over again.
+pub fn with_limits<F, T>(limits: ReadLimits, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let prev = BUDGET.with(|b| b.replace(Some(limits)));
+    let result = f();
+    BUDGET.with(|b| b.set(prev));
+    result
+}
+
+/// Checks a sequence (`Vec`/`BTreeMap`/`HashMap`) length against `max_seq_len` and the
+/// remaining total byte budget before the caller allocates or loops over it.
+pub fn charge_seq_len(len: u64) -> Result<(), Error> {
+    charge(len, |limits| limits.max_seq_len)
+}
+
+/// Checks a string's byte length against `max_string_bytes` and the remaining total
+/// byte budget before the caller allocates a buffer for it.
+pub fn charge_string_bytes(len: u64) -> Result<(), Error> {
+    charge(len, |limits| limits.max_string_bytes)
+}
+
+fn charge(len: u64, per_kind_cap: impl Fn(&ReadLimits) -> u64) -> Result<(), Error> {
+    BUDGET.with(|b| {
+        if let Some(mut limits) = b.get() {
+            if len > per_kind_cap(&limits) || len > limits.max_total_bytes {
+                return Err(Error::LengthLimitExceeded);
+            }
+            limits.max_total_bytes -= len;
+            b.set(Some(limits));
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charge_succeeds_and_depletes_the_total_budget_when_under_the_caps() {
+        let limits = ReadLimits {
+            max_seq_len: 10,
+            max_string_bytes: 10,
+            max_total_bytes: 15,
+        };
+        with_limits(limits, || {
+            charge_seq_len(10).unwrap();
+            // Only 5 bytes were left in the shared total budget after the first charge.
+            assert!(matches!(
+                charge_string_bytes(10),
+                Err(Error::LengthLimitExceeded)
+            ));
+        });
+    }
+
+    #[test]
+    fn charge_rejects_a_length_over_the_per_kind_cap() {
+        let limits = ReadLimits {
+            max_seq_len: 4,
+            ..ReadLimits::default()
+        };
+        with_limits(limits, || {
+            assert!(matches!(charge_seq_len(5), Err(Error::LengthLimitExceeded)));
+        });
+    }
+
+    #[test]
+    fn charge_is_a_no_op_outside_of_with_limits() {
+        assert!(charge_seq_len(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn with_limits_restores_the_previous_scope_on_exit() {
+        let outer = ReadLimits {
+            max_seq_len: 100,
+            ..ReadLimits::default()
+        };
+        with_limits(outer, || {
+            let inner = ReadLimits {
+                max_seq_len: 1,
+                ..ReadLimits::default()
+            };
+            with_limits(inner, || {
+                assert!(matches!(charge_seq_len(2), Err(Error::LengthLimitExceeded)));
+            });
+            charge_seq_len(50).unwrap();
+        });
+    }
+}
@@ -0,0 +1,237 @@
+//! A zero-copy, slice-backed decoding fast path. The generic `Read`-based
+//! [`crate::BinProtRead`] trait issues a separate `read_exact` call per field, which
+//! pays a bounds check and a virtual dispatch per primitive; when the whole value is
+//! already in memory (e.g. a buffer just pulled off a socket), [`BinProtReader`] instead
+//! advances a single offset into the borrowed slice and copies fixed-width primitives
+//! out with one bounds check each. See `benches/decode_bench.rs` for a `Vec<i64>`/
+//! `BTreeMap` comparison of this path against the `Read`-based one.
+
+use crate::Error;
+
+/// A cursor over a borrowed byte slice used to decode binprot values without going
+/// through the `std::io::Read` trait.
+pub struct BinProtReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BinProtReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        BinProtReader { data, pos: 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.remaining() < n {
+            return Err(Error::UnexpectedEof);
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, Error> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    pub fn read_i16_le(&mut self) -> Result<i16, Error> {
+        let bytes = self.take(2)?;
+        let mut buf = [0u8; 2];
+        // SAFETY: `bytes` was just bounds-checked to be exactly 2 bytes long by `take`.
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 2) };
+        Ok(i16::from_le_bytes(buf))
+    }
+
+    pub fn read_u16_le(&mut self) -> Result<u16, Error> {
+        let bytes = self.take(2)?;
+        let mut buf = [0u8; 2];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 2) };
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    pub fn read_i32_le(&mut self) -> Result<i32, Error> {
+        let bytes = self.take(4)?;
+        let mut buf = [0u8; 4];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 4) };
+        Ok(i32::from_le_bytes(buf))
+    }
+
+    pub fn read_u32_le(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        let mut buf = [0u8; 4];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 4) };
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    pub fn read_i64_le(&mut self) -> Result<i64, Error> {
+        let bytes = self.take(8)?;
+        let mut buf = [0u8; 8];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 8) };
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    pub fn read_u64_le(&mut self) -> Result<u64, Error> {
+        let bytes = self.take(8)?;
+        let mut buf = [0u8; 8];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), 8) };
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    pub fn read_f64_le(&mut self) -> Result<f64, Error> {
+        Ok(f64::from_bits(self.read_u64_le()?))
+    }
+
+    fn read_nat0(&mut self) -> Result<u64, Error> {
+        let c = self.read_u8()?;
+        if c < 0x80 {
+            return Ok(c as u64);
+        }
+        match c {
+            crate::int::CODE_INT16 => Ok(self.read_u16_le()? as u64),
+            crate::int::CODE_INT32 => Ok(self.read_u32_le()? as u64),
+            crate::int::CODE_INT64 => self.read_u64_le(),
+            other => Err(Error::InvalidIntTag(other)),
+        }
+    }
+
+    fn read_signed(&mut self) -> Result<i64, Error> {
+        let c = self.read_u8()?;
+        if c < 0x80 {
+            return Ok(c as i64);
+        }
+        match c {
+            crate::int::CODE_NEG_INT8 => Ok(self.read_i8()? as i64),
+            crate::int::CODE_INT16 => Ok(self.read_i16_le()? as i64),
+            crate::int::CODE_INT32 => Ok(self.read_i32_le()? as i64),
+            crate::int::CODE_INT64 => self.read_i64_le(),
+            other => Err(Error::InvalidIntTag(other)),
+        }
+    }
+}
+
+/// Decodes a value directly from a [`BinProtReader`], with no `std::io::Read`
+/// indirection. Implemented only for `Nat0`, `i64`, `f64`, `bool`, and `Vec`/`BTreeMap`
+/// of those; other types have no impl and are decoded through [`crate::BinProtRead`].
+pub trait BinProtReadFast<'a>: Sized {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error>;
+}
+
+impl<'a> BinProtReadFast<'a> for crate::Nat0 {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        Ok(crate::Nat0(r.read_nat0()?))
+    }
+}
+
+impl<'a> BinProtReadFast<'a> for i64 {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        r.read_signed()
+    }
+}
+
+impl<'a> BinProtReadFast<'a> for f64 {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        r.read_f64_le()
+    }
+}
+
+impl<'a> BinProtReadFast<'a> for bool {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        match r.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            c => Err(Error::UnexpectedValueForBool(c)),
+        }
+    }
+}
+
+impl<'a, T: BinProtReadFast<'a>> BinProtReadFast<'a> for Vec<T> {
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        let len = r.read_nat0()?;
+        crate::limits::charge_seq_len(len)?;
+        let mut v = Vec::new();
+        for _ in 0..len {
+            v.push(T::binprot_read_fast(r)?);
+        }
+        Ok(v)
+    }
+}
+
+impl<'a, K: BinProtReadFast<'a> + Ord, V: BinProtReadFast<'a>> BinProtReadFast<'a>
+    for std::collections::BTreeMap<K, V>
+{
+    fn binprot_read_fast(r: &mut BinProtReader<'a>) -> Result<Self, Error> {
+        let len = r.read_nat0()?;
+        crate::limits::charge_seq_len(len)?;
+        let mut res = std::collections::BTreeMap::new();
+        for _ in 0..len {
+            let k = K::binprot_read_fast(r)?;
+            let v = V::binprot_read_fast(r)?;
+            if res.insert(k, v).is_some() {
+                return Err(Error::SameKeyAppearsTwiceInMap);
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinProtRead, BinProtWrite, Nat0};
+
+    fn encode<B: BinProtWrite>(v: &B) -> Vec<u8> {
+        let mut buf = Vec::new();
+        v.binprot_write(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn agrees_with_the_read_based_path_for_a_vec_of_i64() {
+        let value: Vec<i64> = vec![0, 1, -1, 127, 128, -129, i64::MIN, i64::MAX];
+        let bytes = encode(&value);
+
+        let via_read: Vec<i64> = Vec::binprot_read(&mut bytes.as_slice()).unwrap();
+        let via_fast: Vec<i64> = BinProtReadFast::binprot_read_fast(&mut BinProtReader::new(&bytes)).unwrap();
+
+        assert_eq!(via_read, value);
+        assert_eq!(via_fast, value);
+    }
+
+    #[test]
+    fn agrees_with_the_read_based_path_for_a_btree_map() {
+        let mut value = std::collections::BTreeMap::new();
+        value.insert(1i64, 1.5f64);
+        value.insert(-2i64, -3.25f64);
+        let bytes = encode(&value);
+
+        let via_read: std::collections::BTreeMap<i64, f64> =
+            std::collections::BTreeMap::binprot_read(&mut bytes.as_slice()).unwrap();
+        let via_fast: std::collections::BTreeMap<i64, f64> =
+            BinProtReadFast::binprot_read_fast(&mut BinProtReader::new(&bytes)).unwrap();
+
+        assert_eq!(via_read, value);
+        assert_eq!(via_fast, value);
+    }
+
+    #[test]
+    fn rejects_an_invalid_int_tag_byte() {
+        // 0xfb is not one of the recognized width tags (0xff/0xfe/0xfd/0xfc).
+        let bytes = [0xfbu8];
+        let err = Nat0::binprot_read_fast(&mut BinProtReader::new(&bytes)).unwrap_err();
+        assert!(matches!(err, Error::InvalidIntTag(0xfb)));
+    }
+
+    #[test]
+    fn reports_unexpected_eof_on_a_truncated_fixed_width_read() {
+        let bytes = [0u8; 3];
+        let mut r = BinProtReader::new(&bytes);
+        assert!(matches!(r.read_i32_le(), Err(Error::UnexpectedEof)));
+    }
+}
@@ -0,0 +1,228 @@
+//! A canonical description of a type's wire layout, reduced to an MD5 digest so two
+//! peers running this crate can detect that their `BinProtRead`/`BinProtWrite` impls for
+//! a type have drifted apart before one of them corrupts a stream decoding it.
+//!
+//! This is a scheme internal to this crate, not a reimplementation of OCaml bin_prot's
+//! `bin_shape` algorithm — the canonical strings and digests here will not match a
+//! `bin_shape` digest produced by the OCaml library or by a Mina daemon for the "same"
+//! type. Use it to pin compatibility between two builds of a Rust binary that both
+//! depend on this crate, not to compare against an OCaml peer.
+
+/// A canonical description of a type's binprot wire layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    Nat0,
+    Int,
+    Float,
+    Bool,
+    Unit,
+    Str,
+    Char,
+    FixedInt32,
+    FixedInt64,
+    Option(Box<Shape>),
+    List(Box<Shape>),
+    Tuple(Vec<Shape>),
+    Record(Vec<(&'static str, Shape)>),
+    Variant(Vec<(&'static str, Vec<Shape>)>),
+    /// A self-reference inside a recursive type's own shape, keyed by the type's name
+    /// so a cyclic shape terminates instead of recursing forever.
+    Recursive(&'static str),
+}
+
+impl Shape {
+    /// Serializes the shape to a canonical string form, e.g. `list(int)` or
+    /// `record(a:int,b:option(float))`, which is what `digest` is computed over.
+    fn canonical(&self) -> String {
+        match self {
+            Shape::Nat0 => "nat0".to_string(),
+            Shape::Int => "int".to_string(),
+            Shape::Float => "float".to_string(),
+            Shape::Bool => "bool".to_string(),
+            Shape::Unit => "unit".to_string(),
+            Shape::Str => "string".to_string(),
+            Shape::Char => "char".to_string(),
+            Shape::FixedInt32 => "int32".to_string(),
+            Shape::FixedInt64 => "int64".to_string(),
+            Shape::Option(s) => format!("option({})", s.canonical()),
+            Shape::List(s) => format!("list({})", s.canonical()),
+            Shape::Tuple(shapes) => {
+                let parts: Vec<String> = shapes.iter().map(Shape::canonical).collect();
+                format!("tuple({})", parts.join(","))
+            }
+            Shape::Record(fields) => {
+                let parts: Vec<String> = fields
+                    .iter()
+                    .map(|(name, s)| format!("{}:{}", name, s.canonical()))
+                    .collect();
+                format!("record({})", parts.join(","))
+            }
+            Shape::Variant(variants) => {
+                let parts: Vec<String> = variants
+                    .iter()
+                    .map(|(name, args)| {
+                        if args.is_empty() {
+                            name.to_string()
+                        } else {
+                            let arg_parts: Vec<String> =
+                                args.iter().map(Shape::canonical).collect();
+                            format!("{}({})", name, arg_parts.join(","))
+                        }
+                    })
+                    .collect();
+                format!("variant({})", parts.join(","))
+            }
+            Shape::Recursive(name) => format!("@{}", name),
+        }
+    }
+
+    /// MD5 digest of the canonical string form.
+    pub fn digest(&self) -> [u8; 16] {
+        md5::compute(self.canonical().as_bytes()).0
+    }
+
+    /// `digest()` rendered as a lowercase hex string, ready to publish or compare.
+    pub fn digest_hex(&self) -> String {
+        self.digest().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Implemented by every type with a `BinProtRead`/`BinProtWrite` pair so two Rust peers
+/// built against this crate can compare `shape_digest::<T>()` at handshake time before
+/// trusting that they agree on `T`'s wire layout. See the module docs for why this is
+/// not comparable to an OCaml `bin_shape` digest.
+pub trait BinProtShape {
+    fn binprot_shape() -> Shape;
+}
+
+/// The MD5 digest of `T`'s canonical shape, as defined by this module — not an OCaml
+/// `bin_shape` digest.
+pub fn shape_digest<T: BinProtShape>() -> String {
+    T::binprot_shape().digest_hex()
+}
+
+impl BinProtShape for crate::Nat0 {
+    fn binprot_shape() -> Shape {
+        Shape::Nat0
+    }
+}
+
+impl BinProtShape for i64 {
+    fn binprot_shape() -> Shape {
+        Shape::Int
+    }
+}
+
+impl BinProtShape for f64 {
+    fn binprot_shape() -> Shape {
+        Shape::Float
+    }
+}
+
+impl BinProtShape for bool {
+    fn binprot_shape() -> Shape {
+        Shape::Bool
+    }
+}
+
+impl BinProtShape for () {
+    fn binprot_shape() -> Shape {
+        Shape::Unit
+    }
+}
+
+impl BinProtShape for String {
+    fn binprot_shape() -> Shape {
+        Shape::Str
+    }
+}
+
+impl<T: BinProtShape> BinProtShape for Option<T> {
+    fn binprot_shape() -> Shape {
+        Shape::Option(Box::new(T::binprot_shape()))
+    }
+}
+
+impl<T: BinProtShape> BinProtShape for Vec<T> {
+    fn binprot_shape() -> Shape {
+        Shape::List(Box::new(T::binprot_shape()))
+    }
+}
+
+impl<T: BinProtShape> BinProtShape for crate::WithLen<T> {
+    fn binprot_shape() -> Shape {
+        T::binprot_shape()
+    }
+}
+
+impl<K: BinProtShape, V: BinProtShape> BinProtShape for std::collections::BTreeMap<K, V> {
+    fn binprot_shape() -> Shape {
+        Shape::List(Box::new(Shape::Tuple(vec![K::binprot_shape(), V::binprot_shape()])))
+    }
+}
+
+impl<K: BinProtShape, V: BinProtShape> BinProtShape for std::collections::HashMap<K, V> {
+    fn binprot_shape() -> Shape {
+        Shape::List(Box::new(Shape::Tuple(vec![K::binprot_shape(), V::binprot_shape()])))
+    }
+}
+
+macro_rules! tuple_shape_impls {
+    ( $( $name:ident )+ ) => {
+        impl<$($name: BinProtShape),+> BinProtShape for ($($name,)+) {
+            fn binprot_shape() -> Shape {
+                Shape::Tuple(vec![$($name::binprot_shape()),+])
+            }
+        }
+    };
+}
+
+tuple_shape_impls! { A }
+tuple_shape_impls! { A B }
+tuple_shape_impls! { A B C }
+tuple_shape_impls! { A B C D }
+tuple_shape_impls! { A B C D E }
+tuple_shape_impls! { A B C D E F }
+tuple_shape_impls! { A B C D E F G }
+tuple_shape_impls! { A B C D E F G H }
+tuple_shape_impls! { A B C D E F G H I }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_type_digests_the_same_every_time() {
+        assert_eq!(shape_digest::<Vec<i64>>(), shape_digest::<Vec<i64>>());
+    }
+
+    #[test]
+    fn different_element_types_digest_differently() {
+        assert_ne!(shape_digest::<Vec<i64>>(), shape_digest::<Vec<f64>>());
+    }
+
+    #[test]
+    fn reordering_record_fields_digests_differently() {
+        let a = Shape::Record(vec![("x", Shape::Int), ("y", Shape::Float)]);
+        let b = Shape::Record(vec![("y", Shape::Float), ("x", Shape::Int)]);
+        assert_ne!(a.digest_hex(), b.digest_hex());
+    }
+
+    #[test]
+    fn renaming_a_record_field_digests_differently() {
+        let a = Shape::Record(vec![("x", Shape::Int)]);
+        let b = Shape::Record(vec![("z", Shape::Int)]);
+        assert_ne!(a.digest_hex(), b.digest_hex());
+    }
+
+    #[test]
+    fn a_recursive_shape_terminates_via_the_named_back_reference() {
+        // A cons-list-like recursive shape: `Cons(Int, @list) | Nil`. Just building and
+        // digesting it must terminate rather than recursing forever.
+        let list_shape = Shape::Variant(vec![
+            ("Nil", vec![]),
+            ("Cons", vec![Shape::Int, Shape::Recursive("list")]),
+        ]);
+        assert_eq!(list_shape.digest_hex(), list_shape.digest_hex());
+    }
+}
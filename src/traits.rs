@@ -0,0 +1,21 @@
+use crate::Error;
+use std::io::{Read, Write};
+
+/// Serializes a value into the binprot wire format. Requires `BinProtSize` since
+/// `binprot_write_with_size` needs to know a value's encoded length before writing it.
+pub trait BinProtWrite: BinProtSize {
+    fn binprot_write<W: Write>(&self, w: &mut W) -> std::io::Result<()>;
+}
+
+/// Deserializes a value from the binprot wire format.
+pub trait BinProtRead {
+    fn binprot_read<R: Read + ?Sized>(r: &mut R) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+/// Computes the exact number of bytes `binprot_write` would emit for a value,
+/// without actually writing it (used to fill in size prefixes).
+pub trait BinProtSize {
+    fn binprot_size(&self) -> usize;
+}